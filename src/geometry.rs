@@ -67,20 +67,29 @@ impl DMGraph {
     }
 }
 
+/**
+ * builds a DMGraph by connecting every pair of vertices within each
+ * triangle of a flat delaunator-style triangle index list.
+ */
+fn graph_from_triangles(triangles: &[usize], vertex_count: usize) -> DMGraph {
+    let mut graph = DMGraph::empty_of_size(vertex_count);
+    for chunk in triangles.chunks_exact(3) {
+        let a = chunk[0];
+        let b = chunk[1];
+        let c = chunk[2];
+        graph.connect(a.to_owned(), b.to_owned());
+        graph.connect(a.to_owned(), c.to_owned());
+        graph.connect(b.to_owned(), c.to_owned());
+    }
+    graph
+}
+
 byond_fn!(
     fn geometry_delaunay_triangulate_to_graph(point_json) {
         let points: Vec<DMVec2> = serde_json::from_str(point_json).unwrap();
         let transmuted: Vec<Point> = points.iter().map(|p| Point{x: p.x, y: p.y}).collect();
         let triangulated = delaunator::triangulate(&transmuted);
-        let mut constructing = DMGraph::empty_of_size(points.len());
-        for chunk in triangulated.triangles.chunks_exact(3) {
-            let a = chunk[0];
-            let b = chunk[1];
-            let c = chunk[2];
-            constructing.connect(a.to_owned(), b.to_owned());
-            constructing.connect(a.to_owned(), c.to_owned());
-            constructing.connect(b.to_owned(), c.to_owned());
-        };
+        let constructing = graph_from_triangles(&triangulated.triangles, points.len());
         Some(serde_json::to_string(&constructing).unwrap())
     }
 );
@@ -93,6 +102,7 @@ struct DMDelaunayVoronoiCall {
     area: f64,
     cell: f64,
     margin: f64,
+    relax: u32,
     points: Vec<DMVec2>,
 }
 
@@ -104,68 +114,919 @@ struct DMDelaunayVoronoiReturn {
     graph: DMGraph,
     areas: Vec<Option<f64>>,
     cells: Vec<Option<Vec<DMVec2>>>,
+    sites: Vec<DMVec2>,
+}
+
+fn delaunay_voronoi_graph(points: &Vec<DMVec2>, area: f64, cell: f64, margin: f64, relax: u32) -> DMDelaunayVoronoiReturn {
+    let transmuted: Vec<Point> = points.iter().map(|p| Point{x: p.x, y: p.y}).collect();
+    let mut x_low: f64 = f64::INFINITY;
+    let mut x_high: f64 = -f64::INFINITY;
+    let mut y_low: f64 = f64::INFINITY;
+    let mut y_high: f64 = -f64::INFINITY;
+    for point in transmuted.iter() {
+        x_low = x_low.min(point.x);
+        x_high = x_high.max(point.x);
+        y_low = y_low.min(point.y);
+        y_high = y_high.max(point.y);
+    }
+    let center_point = Point{x: x_low + (x_high - x_low) * 0.5, y: y_low + (y_high - y_low) * 0.5};
+    let requires_area = area != 0_f64;
+    let requires_cell = cell != 0_f64;
+    let computed = voronoice::VoronoiBuilder::default()
+        .set_sites(transmuted)
+        .set_bounding_box(
+            BoundingBox::new(center_point, (x_high - x_low) + margin * 2_f64, (y_high - y_low) + margin * 2_f64)
+        )
+        .set_lloyd_relaxation_iterations(relax as usize)
+        .build().unwrap();
+    let count = points.len();
+    let constructing_graph = graph_from_triangles(&computed.triangulation().triangles, count);
+    let mut areas_constructed: Vec<Option<f64>> = vec![Option::None; count];
+    let mut cells_constructed: Vec<Option<Vec<DMVec2>>> = vec![Option::None; count];
+    for i in 0..count {
+        let voronoi_cell = computed.cell(i);
+        let mut vertices_constructed: Vec<DMVec2> = Vec::new();
+        for vertex in voronoi_cell.iter_vertices() {
+            vertices_constructed.push(
+                DMVec2{
+                    x: vertex.x,
+                    y: vertex.y,
+                    area: Option::None,
+                    cell: Option::None,
+                }
+            );
+        }
+        if requires_area {
+            areas_constructed[i] = Some(DMVec2::polygon_area(&vertices_constructed));
+        }
+        if requires_cell {
+            cells_constructed[i] = Some(vertices_constructed);
+        }
+    }
+    let sites_constructed: Vec<DMVec2> = computed.sites().iter().map(|p| DMVec2{
+        x: p.x,
+        y: p.y,
+        area: Option::None,
+        cell: Option::None,
+    }).collect();
+    DMDelaunayVoronoiReturn{
+        graph: constructing_graph,
+        areas: areas_constructed,
+        cells: cells_constructed,
+        sites: sites_constructed,
+    }
 }
 
 byond_fn!(
     fn geometry_delaunay_voronoi_graph(packed) {
         let unpacked: DMDelaunayVoronoiCall = serde_json::from_str(packed).unwrap();
-        let transmuted: Vec<Point> = unpacked.points.iter().map(|p| Point{x: p.x, y: p.y}).collect();
-        let mut x_low: f64 = f64::INFINITY;
-        let mut x_high: f64 = -f64::INFINITY;
-        let mut y_low: f64 = f64::INFINITY;
-        let mut y_high: f64 = -f64::INFINITY;
-        let margin = unpacked.margin;
-        for point in transmuted.iter() {
-            x_low = x_low.min(point.x);
-            x_high = x_high.max(point.x);
-            y_low = y_low.min(point.y);
-            y_high = y_high.max(point.y);
-        }
-        let center_point = Point{x: x_low + (x_high - x_low) * 0.5, y: y_low + (y_high - y_low) * 0.5};
-        let requires_area = unpacked.area != 0_f64;
-        let requires_cell = unpacked.cell != 0_f64;
-        let computed = voronoice::VoronoiBuilder::default()
-            .set_sites(transmuted)
-            .set_bounding_box(
-                BoundingBox::new(center_point, (x_high - x_low) + margin * 2_f64, (y_high - y_low) + margin * 2_f64)
-            )
-            .build().unwrap();
-        let count = unpacked.points.len();
-        let mut constructing_graph = DMGraph::empty_of_size(count);
-        for chunk in computed.triangulation().triangles.chunks_exact(3) {
-            let a = chunk[0];
-            let b = chunk[1];
-            let c = chunk[2];
-            constructing_graph.connect(a.to_owned(), b.to_owned());
-            constructing_graph.connect(a.to_owned(), c.to_owned());
-            constructing_graph.connect(b.to_owned(), c.to_owned());
+        let result = delaunay_voronoi_graph(&unpacked.points, unpacked.area, unpacked.cell, unpacked.margin, unpacked.relax);
+        Some(serde_json::to_string(&result).unwrap())
+    }
+);
+
+/**
+ * simple union-find with path compression, used by the connected-components
+ * and minimum-spanning-tree graph ops below.
+ */
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(size: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..size).collect(),
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        if root_a == root_b {
+            return false;
+        }
+        self.parent[root_a] = root_b;
+        true
+    }
+}
+
+fn connected_components(graph: &DMGraph) -> Vec<usize> {
+    let mut union_find = UnionFind::new(graph.count);
+    for a in 0..graph.count {
+        for &b in graph.edges[a].iter() {
+            union_find.union(a, b);
+        }
+    }
+    let mut label_map: std::collections::HashMap<usize, usize> = std::collections::HashMap::new();
+    let mut labels: Vec<usize> = vec![0; graph.count];
+    for i in 0..graph.count {
+        let root = union_find.find(i);
+        let next_label = label_map.len();
+        labels[i] = *label_map.entry(root).or_insert(next_label);
+    }
+    labels
+}
+
+byond_fn!(
+    fn geometry_graph_connected_components(graph_json) {
+        let graph: DMGraph = serde_json::from_str(graph_json).unwrap();
+        Some(serde_json::to_string(&connected_components(&graph)).unwrap())
+    }
+);
+
+fn reconstruct_path(predecessor: &Vec<Option<usize>>, from: usize, to: usize) -> Vec<usize> {
+    let mut path = vec![to];
+    let mut current = to;
+    while current != from {
+        current = predecessor[current].unwrap();
+        path.push(current);
+    }
+    path.reverse();
+    path
+}
+
+fn bfs_path(graph: &DMGraph, from: usize, to: usize) -> Option<Vec<usize>> {
+    if from >= graph.count || to >= graph.count {
+        return None;
+    }
+    if from == to {
+        return Some(vec![from]);
+    }
+    let mut visited = vec![false; graph.count];
+    let mut predecessor: Vec<Option<usize>> = vec![None; graph.count];
+    let mut queue: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    visited[from] = true;
+    queue.push_back(from);
+    while let Some(current) = queue.pop_front() {
+        for &neighbor in graph.edges[current].iter() {
+            if visited[neighbor] {
+                continue;
+            }
+            visited[neighbor] = true;
+            predecessor[neighbor] = Some(current);
+            if neighbor == to {
+                return Some(reconstruct_path(&predecessor, from, to));
+            }
+            queue.push_back(neighbor);
+        }
+    }
+    None
+}
+
+#[derive(PartialEq)]
+struct DijkstraState {
+    cost: f64,
+    vertex: usize,
+}
+
+impl Eq for DijkstraState {}
+
+impl Ord for DijkstraState {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // reversed so BinaryHeap (a max-heap) pops the smallest cost first
+        other.cost.partial_cmp(&self.cost).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+impl PartialOrd for DijkstraState {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn dijkstra_path(graph: &DMGraph, positions: &Vec<DMVec2>, from: usize, to: usize) -> Option<Vec<usize>> {
+    if from >= graph.count || to >= graph.count || positions.len() != graph.count {
+        return None;
+    }
+    let mut distances = vec![f64::INFINITY; graph.count];
+    let mut predecessor: Vec<Option<usize>> = vec![None; graph.count];
+    let mut heap = std::collections::BinaryHeap::new();
+    distances[from] = 0_f64;
+    heap.push(DijkstraState{cost: 0_f64, vertex: from});
+    while let Some(DijkstraState{cost, vertex}) = heap.pop() {
+        if vertex == to {
+            break;
+        }
+        if cost > distances[vertex] {
+            continue;
+        }
+        for &neighbor in graph.edges[vertex].iter() {
+            let dx = positions[vertex].x - positions[neighbor].x;
+            let dy = positions[vertex].y - positions[neighbor].y;
+            let next_cost = cost + (dx * dx + dy * dy).sqrt();
+            if next_cost < distances[neighbor] {
+                distances[neighbor] = next_cost;
+                predecessor[neighbor] = Some(vertex);
+                heap.push(DijkstraState{cost: next_cost, vertex: neighbor});
+            }
+        }
+    }
+    if distances[to].is_finite() {
+        Some(reconstruct_path(&predecessor, from, to))
+    } else {
+        None
+    }
+}
+
+/**
+ * call data
+ */
+#[derive(Deserialize)]
+struct DMGraphShortestPathCall {
+    graph: DMGraph,
+    from: usize,
+    to: usize,
+    positions: Option<Vec<DMVec2>>,
+}
+
+byond_fn!(
+    fn geometry_graph_shortest_path(packed) {
+        let unpacked: DMGraphShortestPathCall = serde_json::from_str(packed).unwrap();
+        let path = match &unpacked.positions {
+            Some(positions) => dijkstra_path(&unpacked.graph, positions, unpacked.from, unpacked.to),
+            None => bfs_path(&unpacked.graph, unpacked.from, unpacked.to),
         };
-        let mut areas_constructed: Vec<Option<f64>> = vec![Option::None; count];
-        let mut cells_constructed: Vec<Option<Vec<DMVec2>>> = vec![Option::None; count];
-        for i in 0..count {
-            let cell = computed.cell(i);
-            let mut vertices_constructed: Vec<DMVec2> = Vec::new();
-            for vertex in cell.iter_vertices() {
-                vertices_constructed.push(
-                    DMVec2{
-                        x: vertex.x,
-                        y: vertex.y,
-                        area: Option::None,
-                        cell: Option::None,
+        Some(serde_json::to_string(&path).unwrap())
+    }
+);
+
+/**
+ * call data
+ */
+#[derive(Deserialize)]
+struct DMGraphMinimumSpanningTreeCall {
+    graph: DMGraph,
+    positions: Vec<DMVec2>,
+}
+
+fn minimum_spanning_tree(graph: &DMGraph, positions: &Vec<DMVec2>) -> Option<DMGraph> {
+    if positions.len() != graph.count {
+        return None;
+    }
+    let mut candidate_edges: Vec<(f64, usize, usize)> = Vec::new();
+    for a in 0..graph.count {
+        for &b in graph.edges[a].iter() {
+            if a < b {
+                let dx = positions[a].x - positions[b].x;
+                let dy = positions[a].y - positions[b].y;
+                candidate_edges.push(((dx * dx + dy * dy).sqrt(), a, b));
+            }
+        }
+    }
+    candidate_edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut union_find = UnionFind::new(graph.count);
+    let mut mst = DMGraph::empty_of_size(graph.count);
+    for (_, a, b) in candidate_edges {
+        if union_find.union(a, b) {
+            mst.connect(a, b);
+        }
+    }
+    Some(mst)
+}
+
+byond_fn!(
+    fn geometry_graph_minimum_spanning_tree(packed) {
+        let unpacked: DMGraphMinimumSpanningTreeCall = serde_json::from_str(packed).unwrap();
+        let mst = minimum_spanning_tree(&unpacked.graph, &unpacked.positions)?;
+        Some(serde_json::to_string(&mst).unwrap())
+    }
+);
+
+fn euler_path(graph: &DMGraph) -> Option<Vec<usize>> {
+    let degree: Vec<usize> = graph.edges.iter().map(|e| e.len()).collect();
+    let non_trivial: Vec<usize> = (0..graph.count).filter(|&i| degree[i] > 0).collect();
+    if non_trivial.is_empty() {
+        return None;
+    }
+    let mut visited = vec![false; graph.count];
+    let mut stack = vec![non_trivial[0]];
+    visited[non_trivial[0]] = true;
+    while let Some(current) = stack.pop() {
+        for &neighbor in graph.edges[current].iter() {
+            if !visited[neighbor] {
+                visited[neighbor] = true;
+                stack.push(neighbor);
+            }
+        }
+    }
+    if non_trivial.iter().any(|&v| !visited[v]) {
+        return None;
+    }
+    let odd_vertices: Vec<usize> = non_trivial.iter().cloned().filter(|&v| degree[v] % 2 == 1).collect();
+    let start = match odd_vertices.len() {
+        0 => non_trivial[0],
+        2 => odd_vertices[0],
+        _ => return None,
+    };
+    // Hierholzer's algorithm: walk unused edges until stuck, then splice
+    // sub-tours in by popping dead ends onto the output in reverse order.
+    let mut adjacency = graph.edges.clone();
+    let mut walk_stack = vec![start];
+    let mut path: Vec<usize> = Vec::new();
+    while let Some(&current) = walk_stack.last() {
+        if let Some(next) = adjacency[current].pop() {
+            if let Some(pos) = adjacency[next].iter().position(|&v| v == current) {
+                adjacency[next].remove(pos);
+            }
+            walk_stack.push(next);
+        } else {
+            path.push(walk_stack.pop().unwrap());
+        }
+    }
+    path.reverse();
+    Some(path)
+}
+
+byond_fn!(
+    fn geometry_graph_euler_path(graph_json) {
+        let graph: DMGraph = serde_json::from_str(graph_json).unwrap();
+        Some(serde_json::to_string(&euler_path(&graph)).unwrap())
+    }
+);
+
+fn point_in_polygon(point: &DMVec2, polygon: &Vec<DMVec2>) -> bool {
+    let size = polygon.len();
+    if size < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = size - 1;
+    for i in 0..size {
+        let vi = &polygon[i];
+        let vj = &polygon[j];
+        if (vi.y > point.y) != (vj.y > point.y)
+            && point.x < (vj.x - vi.x) * (point.y - vi.y) / (vj.y - vi.y) + vi.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn sample_boundary(polygon: &Vec<DMVec2>, spacing: f64) -> Vec<Point> {
+    let size = polygon.len();
+    let mut samples: Vec<Point> = Vec::new();
+    for i in 0..size {
+        let start = &polygon[i];
+        let end = &polygon[(i + 1) % size];
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+        let length = (dx * dx + dy * dy).sqrt();
+        let steps = ((length / spacing).round() as usize).max(1);
+        for step in 0..steps {
+            let t = step as f64 / steps as f64;
+            samples.push(Point { x: start.x + dx * t, y: start.y + dy * t });
+        }
+    }
+    samples
+}
+
+/**
+ * keys a vertex lookup table by rounded coordinates so Voronoi vertices
+ * shared between adjacent cells collapse to a single graph index.
+ */
+fn vertex_key(point: &Point) -> (i64, i64) {
+    ((point.x * 1e6).round() as i64, (point.y * 1e6).round() as i64)
+}
+
+/**
+ * call data
+ */
+#[derive(Deserialize)]
+struct DMPolygonCenterlineCall {
+    polygon: Vec<DMVec2>,
+    spacing: f64,
+    prune: f64,
+}
+
+/**
+ * call return
+ */
+#[derive(Serialize)]
+struct DMPolygonCenterlineReturn {
+    graph: DMGraph,
+    vertices: Vec<DMVec2>,
+}
+
+fn polygon_centerline(polygon: &Vec<DMVec2>, spacing: f64, prune: f64) -> Option<DMPolygonCenterlineReturn> {
+    if spacing <= 0_f64 || polygon.len() < 3 {
+        return None;
+    }
+    let samples = sample_boundary(polygon, spacing);
+    let mut x_low: f64 = f64::INFINITY;
+    let mut x_high: f64 = -f64::INFINITY;
+    let mut y_low: f64 = f64::INFINITY;
+    let mut y_high: f64 = -f64::INFINITY;
+    for point in polygon.iter() {
+        x_low = x_low.min(point.x);
+        x_high = x_high.max(point.x);
+        y_low = y_low.min(point.y);
+        y_high = y_high.max(point.y);
+    }
+    let margin = spacing * 2_f64;
+    let center_point = Point{x: x_low + (x_high - x_low) * 0.5, y: y_low + (y_high - y_low) * 0.5};
+    let computed = voronoice::VoronoiBuilder::default()
+        .set_sites(samples.clone())
+        .set_bounding_box(
+            BoundingBox::new(center_point, (x_high - x_low) + margin * 2_f64, (y_high - y_low) + margin * 2_f64)
+        )
+        .build().unwrap();
+    let mut vertex_lookup: std::collections::HashMap<(i64, i64), usize> = std::collections::HashMap::new();
+    let mut vertices: Vec<DMVec2> = Vec::new();
+    let mut edges: std::collections::HashSet<(usize, usize)> = std::collections::HashSet::new();
+    for i in 0..samples.len() {
+        let cell = computed.cell(i);
+        let cell_vertices: Vec<Point> = cell.iter_vertices().map(|v| Point{x: v.x, y: v.y}).collect();
+        let count = cell_vertices.len();
+        let mut indices: Vec<usize> = Vec::with_capacity(count);
+        for vertex in cell_vertices.iter() {
+            let key = vertex_key(vertex);
+            let index = *vertex_lookup.entry(key).or_insert_with(|| {
+                vertices.push(DMVec2{x: vertex.x, y: vertex.y, area: Option::None, cell: Option::None});
+                vertices.len() - 1
+            });
+            indices.push(index);
+        }
+        for j in 0..count {
+            let a = indices[j];
+            let b = indices[(j + 1) % count];
+            if a == b {
+                continue;
+            }
+            let point_a = DMVec2{x: vertices[a].x, y: vertices[a].y, area: Option::None, cell: Option::None};
+            let point_b = DMVec2{x: vertices[b].x, y: vertices[b].y, area: Option::None, cell: Option::None};
+            if point_in_polygon(&point_a, polygon) && point_in_polygon(&point_b, polygon) {
+                edges.insert(if a < b { (a, b) } else { (b, a) });
+            }
+        }
+    }
+    let mut graph = DMGraph::empty_of_size(vertices.len());
+    for &(a, b) in edges.iter() {
+        graph.connect(a, b);
+    }
+    if prune > 0_f64 {
+        loop {
+            let mut pruned_any = false;
+            for v in 0..graph.count {
+                if graph.edges[v].len() == 1 {
+                    let neighbor = graph.edges[v][0];
+                    let dx = vertices[v].x - vertices[neighbor].x;
+                    let dy = vertices[v].y - vertices[neighbor].y;
+                    if (dx * dx + dy * dy).sqrt() < prune {
+                        graph.edges[v].clear();
+                        if let Some(pos) = graph.edges[neighbor].iter().position(|&e| e == v) {
+                            graph.edges[neighbor].remove(pos);
+                        }
+                        pruned_any = true;
                     }
-                );
+                }
+            }
+            if !pruned_any {
+                break;
+            }
+        }
+    }
+    Some(DMPolygonCenterlineReturn{ graph, vertices })
+}
+
+byond_fn!(
+    fn geometry_polygon_centerline(packed) {
+        let unpacked: DMPolygonCenterlineCall = serde_json::from_str(packed).unwrap();
+        let result = polygon_centerline(&unpacked.polygon, unpacked.spacing, unpacked.prune)?;
+        Some(serde_json::to_string(&result).unwrap())
+    }
+);
+
+#[derive(Deserialize)]
+struct DMSegment {
+    a: DMVec2,
+    b: DMVec2,
+}
+
+fn point_on_segment(point: &DMVec2, segment: &DMSegment) -> bool {
+    let sx = segment.b.x - segment.a.x;
+    let sy = segment.b.y - segment.a.y;
+    let cross = sx * (point.y - segment.a.y) - sy * (point.x - segment.a.x);
+    if cross.abs() > 1e-9 {
+        return false;
+    }
+    let dot = (point.x - segment.a.x) * sx + (point.y - segment.a.y) * sy;
+    let length_squared = sx * sx + sy * sy;
+    dot >= 0_f64 && dot <= length_squared
+}
+
+/**
+ * casts a ray from origin at the given angle and returns the nearest point
+ * where it hits one of the segments, falling back to far_radius along the
+ * ray when nothing is hit (keeps open directions finite).
+ */
+fn cast_ray(origin: &DMVec2, angle: f64, segments: &Vec<&DMSegment>, far_radius: f64) -> DMVec2 {
+    let dx = angle.cos();
+    let dy = angle.sin();
+    let mut nearest_t = far_radius;
+    for segment in segments.iter() {
+        let sx = segment.b.x - segment.a.x;
+        let sy = segment.b.y - segment.a.y;
+        let denom = dx * sy - dy * sx;
+        if denom.abs() < 1e-12 {
+            continue;
+        }
+        let ox = segment.a.x - origin.x;
+        let oy = segment.a.y - origin.y;
+        let t = (ox * sy - oy * sx) / denom;
+        let u = (ox * dy - oy * dx) / denom;
+        if t >= 0_f64 && u >= 0_f64 && u <= 1_f64 && t < nearest_t {
+            nearest_t = t;
+        }
+    }
+    DMVec2{x: origin.x + dx * nearest_t, y: origin.y + dy * nearest_t, area: Option::None, cell: Option::None}
+}
+
+/**
+ * call data
+ */
+#[derive(Deserialize)]
+struct DMVisibilityPolygonCall {
+    origin: DMVec2,
+    segments: Vec<DMSegment>,
+}
+
+fn visibility_polygon(origin: &DMVec2, all_segments: &Vec<DMSegment>) -> Vec<DMVec2> {
+    let segments: Vec<&DMSegment> = all_segments.iter()
+        .filter(|segment| !point_on_segment(origin, segment))
+        .collect();
+    let mut far_radius: f64 = 1_f64;
+    for segment in all_segments.iter() {
+        far_radius = far_radius.max(((segment.a.x - origin.x).powi(2) + (segment.a.y - origin.y).powi(2)).sqrt());
+        far_radius = far_radius.max(((segment.b.x - origin.x).powi(2) + (segment.b.y - origin.y).powi(2)).sqrt());
+    }
+    far_radius = far_radius * 2_f64 + 1_f64;
+    let epsilon = 1e-4;
+    let mut angles: Vec<f64> = Vec::new();
+    for segment in segments.iter() {
+        angles.push((segment.a.y - origin.y).atan2(segment.a.x - origin.x));
+        angles.push((segment.b.y - origin.y).atan2(segment.b.x - origin.x));
+    }
+    if angles.is_empty() {
+        // no walls in range: fall back to the cardinal directions so an
+        // open area still produces a finite far-radius polygon
+        angles = vec![0_f64, std::f64::consts::FRAC_PI_2, std::f64::consts::PI, -std::f64::consts::FRAC_PI_2];
+    }
+    angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    angles.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+    let mut hits: Vec<(f64, DMVec2)> = Vec::new();
+    for &angle in angles.iter() {
+        for offset in [-epsilon, 0_f64, epsilon].iter() {
+            let ray_angle = angle + offset;
+            let hit = cast_ray(origin, ray_angle, &segments, far_radius);
+            hits.push((ray_angle, hit));
+        }
+    }
+    hits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    hits.into_iter().map(|(_, point)| point).collect()
+}
+
+byond_fn!(
+    fn geometry_visibility_polygon(packed) {
+        let unpacked: DMVisibilityPolygonCall = serde_json::from_str(packed).unwrap();
+        let polygon = visibility_polygon(&unpacked.origin, &unpacked.segments);
+        Some(serde_json::to_string(&polygon).unwrap())
+    }
+);
+
+fn distance_squared(a: &DMVec2, b: &DMVec2) -> f64 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    dx * dx + dy * dy
+}
+
+fn triangulate_graph(points: &Vec<DMVec2>) -> DMGraph {
+    let transmuted: Vec<Point> = points.iter().map(|p| Point{x: p.x, y: p.y}).collect();
+    let triangulated = delaunator::triangulate(&transmuted);
+    graph_from_triangles(&triangulated.triangles, points.len())
+}
+
+fn gabriel_graph(points: &Vec<DMVec2>) -> DMGraph {
+    let delaunay = triangulate_graph(points);
+    let mut gabriel = DMGraph::empty_of_size(points.len());
+    for a in 0..delaunay.count {
+        for &b in delaunay.edges[a].iter() {
+            if a >= b {
+                continue;
             }
-            if requires_area {
-                areas_constructed[i] = Some(DMVec2::polygon_area(&vertices_constructed));
+            let edge_distance_squared = distance_squared(&points[a], &points[b]);
+            let mut keep = true;
+            for &c in delaunay.edges[a].iter().chain(delaunay.edges[b].iter()) {
+                if c == a || c == b {
+                    continue;
+                }
+                if distance_squared(&points[a], &points[c]) + distance_squared(&points[b], &points[c]) < edge_distance_squared {
+                    keep = false;
+                    break;
+                }
             }
-            if requires_cell {
-                cells_constructed[i] = Some(vertices_constructed);
+            if keep {
+                gabriel.connect(a, b);
             }
         }
-        Some(serde_json::to_string(&DMDelaunayVoronoiReturn{
-            graph: constructing_graph,
-            areas: areas_constructed,
-            cells: cells_constructed,
-        }).unwrap())
+    }
+    gabriel
+}
+
+fn relative_neighborhood_graph(points: &Vec<DMVec2>) -> DMGraph {
+    let delaunay = triangulate_graph(points);
+    let mut rng_graph = DMGraph::empty_of_size(points.len());
+    for a in 0..delaunay.count {
+        for &b in delaunay.edges[a].iter() {
+            if a >= b {
+                continue;
+            }
+            let edge_distance = distance_squared(&points[a], &points[b]).sqrt();
+            let mut keep = true;
+            for &c in delaunay.edges[a].iter().chain(delaunay.edges[b].iter()) {
+                if c == a || c == b {
+                    continue;
+                }
+                let dist_ac = distance_squared(&points[a], &points[c]).sqrt();
+                let dist_bc = distance_squared(&points[b], &points[c]).sqrt();
+                if dist_ac.max(dist_bc) < edge_distance {
+                    keep = false;
+                    break;
+                }
+            }
+            if keep {
+                rng_graph.connect(a, b);
+            }
+        }
+    }
+    rng_graph
+}
+
+byond_fn!(
+    fn geometry_delaunay_gabriel_graph(point_json) {
+        let points: Vec<DMVec2> = serde_json::from_str(point_json).unwrap();
+        Some(serde_json::to_string(&gabriel_graph(&points)).unwrap())
+    }
+);
+
+byond_fn!(
+    fn geometry_delaunay_relative_neighborhood_graph(point_json) {
+        let points: Vec<DMVec2> = serde_json::from_str(point_json).unwrap();
+        Some(serde_json::to_string(&relative_neighborhood_graph(&points)).unwrap())
     }
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec2(x: f64, y: f64) -> DMVec2 {
+        DMVec2 { x, y, area: None, cell: None }
+    }
+
+    fn graph_from_edges(count: usize, edges: &[(usize, usize)]) -> DMGraph {
+        let mut graph = DMGraph::empty_of_size(count);
+        for &(a, b) in edges {
+            graph.connect(a, b);
+        }
+        graph
+    }
+
+    #[test]
+    fn connected_components_splits_disjoint_graphs() {
+        let graph = graph_from_edges(5, &[(0, 1), (1, 2), (3, 4)]);
+        let labels = connected_components(&graph);
+        assert_eq!(labels[0], labels[1]);
+        assert_eq!(labels[1], labels[2]);
+        assert_eq!(labels[3], labels[4]);
+        assert_ne!(labels[0], labels[3]);
+    }
+
+    #[test]
+    fn shortest_path_hop_count_bfs() {
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        let path = bfs_path(&graph, 0, 3).unwrap();
+        assert_eq!(path, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn shortest_path_returns_none_when_unreachable() {
+        let graph = graph_from_edges(4, &[(0, 1), (2, 3)]);
+        assert_eq!(bfs_path(&graph, 0, 3), None);
+    }
+
+    #[test]
+    fn shortest_path_bfs_returns_none_for_out_of_range_indices() {
+        let graph = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        assert_eq!(bfs_path(&graph, 0, 9), None);
+        assert_eq!(bfs_path(&graph, 9, 0), None);
+    }
+
+    #[test]
+    fn shortest_path_dijkstra_returns_none_for_out_of_range_or_mismatched_positions() {
+        let graph = graph_from_edges(3, &[(0, 1), (1, 2)]);
+        let positions = vec![vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(2.0, 0.0)];
+        assert_eq!(dijkstra_path(&graph, &positions, 0, 9), None);
+        let short_positions = vec![vec2(0.0, 0.0), vec2(1.0, 0.0)];
+        assert_eq!(dijkstra_path(&graph, &short_positions, 0, 2), None);
+    }
+
+    #[test]
+    fn shortest_path_dijkstra_picks_cheaper_detour() {
+        // 0 -> 1 -> 3 is the long way around; 0 -> 2 -> 3 is much shorter
+        let graph = graph_from_edges(4, &[(0, 1), (1, 3), (0, 2), (2, 3)]);
+        let positions = vec![vec2(0.0, 0.0), vec2(0.0, 10.0), vec2(1.0, 0.0), vec2(1.0, 1.0)];
+        let path = dijkstra_path(&graph, &positions, 0, 3).unwrap();
+        assert_eq!(path, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn minimum_spanning_tree_has_n_minus_one_edges_and_minimal_weight() {
+        let positions = vec![vec2(0.0, 0.0), vec2(3.0, 0.0), vec2(3.0, 4.0), vec2(0.0, 4.0)];
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2)]);
+        let mst = minimum_spanning_tree(&graph, &positions).unwrap();
+        let mut edge_count = 0;
+        let mut total_weight = 0_f64;
+        for a in 0..mst.count {
+            for &b in mst.edges[a].iter() {
+                if b > a {
+                    edge_count += 1;
+                    let dx = positions[a].x - positions[b].x;
+                    let dy = positions[a].y - positions[b].y;
+                    total_weight += (dx * dx + dy * dy).sqrt();
+                }
+            }
+        }
+        assert_eq!(edge_count, 3);
+        assert!((total_weight - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn minimum_spanning_tree_returns_none_when_positions_mismatch_graph_size() {
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        let positions = vec![vec2(0.0, 0.0), vec2(1.0, 0.0)];
+        assert!(minimum_spanning_tree(&graph, &positions).is_none());
+    }
+
+    #[test]
+    fn euler_path_finds_circuit_on_a_cycle() {
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let path = euler_path(&graph).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.first(), path.last());
+    }
+
+    #[test]
+    fn euler_path_finds_open_path() {
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        let path = euler_path(&graph).unwrap();
+        assert_eq!(path.len(), 4);
+        assert!((path[0] == 0 && path[3] == 3) || (path[0] == 3 && path[3] == 0));
+    }
+
+    #[test]
+    fn euler_path_open_path_with_pendant() {
+        // triangle (all even degree) plus a pendant edge off vertex 0, which
+        // makes 0 and 3 the only odd-degree vertices
+        let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 0), (0, 3)]);
+        let path = euler_path(&graph).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!((path[0] == 0 && path[4] == 3) || (path[0] == 3 && path[4] == 0));
+    }
+
+    #[test]
+    fn euler_path_none_for_too_many_odd_vertices() {
+        // a 4-leaf star: the center has even degree but all 4 leaves are odd
+        let graph = graph_from_edges(5, &[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        assert_eq!(euler_path(&graph), None);
+    }
+
+    #[test]
+    fn euler_path_none_when_disconnected() {
+        let graph = graph_from_edges(4, &[(0, 1), (2, 3)]);
+        assert_eq!(euler_path(&graph), None);
+    }
+
+    #[test]
+    fn point_in_polygon_rejects_too_few_vertices() {
+        assert!(!point_in_polygon(&vec2(0.0, 0.0), &Vec::new()));
+    }
+
+    #[test]
+    fn point_in_polygon_basic_square() {
+        let square = vec![vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 10.0), vec2(0.0, 10.0)];
+        assert!(point_in_polygon(&vec2(5.0, 5.0), &square));
+        assert!(!point_in_polygon(&vec2(20.0, 20.0), &square));
+    }
+
+    #[test]
+    fn sample_boundary_respects_spacing() {
+        let square = vec![vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 10.0), vec2(0.0, 10.0)];
+        let samples = sample_boundary(&square, 5.0);
+        assert_eq!(samples.len(), 8);
+    }
+
+    #[test]
+    fn polygon_centerline_rejects_zero_spacing() {
+        let polygon = vec![vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 10.0), vec2(0.0, 10.0)];
+        assert!(polygon_centerline(&polygon, 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn polygon_centerline_rejects_degenerate_polygon() {
+        let polygon = vec![vec2(0.0, 0.0), vec2(1.0, 0.0)];
+        assert!(polygon_centerline(&polygon, 1.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn visibility_polygon_open_area_returns_bounded_polygon() {
+        let origin = vec2(0.0, 0.0);
+        let polygon = visibility_polygon(&origin, &Vec::new());
+        assert!(!polygon.is_empty());
+    }
+
+    #[test]
+    fn visibility_polygon_blocked_by_nearby_wall() {
+        let origin = vec2(0.0, 0.0);
+        let segments = vec![DMSegment{a: vec2(-5.0, 5.0), b: vec2(5.0, 5.0)}];
+        let polygon = visibility_polygon(&origin, &segments);
+        assert!(!polygon.is_empty());
+        // every hit strictly inside the wall's angular span must stop at the wall
+        let mut behind_wall = 0;
+        for point in polygon.iter() {
+            let angle = point.y.atan2(point.x);
+            if angle > std::f64::consts::FRAC_PI_4 + 1e-6 && angle < 3.0 * std::f64::consts::FRAC_PI_4 - 1e-6 {
+                behind_wall += 1;
+                assert!(point.y <= 5.0 + 1e-6);
+            }
+        }
+        assert!(behind_wall > 0);
+    }
+
+    #[test]
+    fn gabriel_graph_is_subset_of_delaunay() {
+        let points = vec![vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0), vec2(0.0, 1.0)];
+        let gabriel = gabriel_graph(&points);
+        let delaunay = triangulate_graph(&points);
+        for a in 0..gabriel.count {
+            for &b in gabriel.edges[a].iter() {
+                assert!(delaunay.edges[a].contains(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn relative_neighborhood_graph_is_subset_of_gabriel() {
+        let points = vec![vec2(0.0, 0.0), vec2(2.0, 0.0), vec2(1.0, 1.0), vec2(1.0, -1.0)];
+        let gabriel = gabriel_graph(&points);
+        let rng = relative_neighborhood_graph(&points);
+        for a in 0..rng.count {
+            for &b in rng.edges[a].iter() {
+                assert!(gabriel.edges[a].contains(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn delaunay_voronoi_graph_zero_relax_keeps_sites_unchanged() {
+        let points = vec![
+            vec2(0.0, 0.0),
+            vec2(4.0, 0.0),
+            vec2(4.0, 4.0),
+            vec2(0.0, 4.0),
+            vec2(2.0, 2.0),
+        ];
+        let result = delaunay_voronoi_graph(&points, 0_f64, 0_f64, 1_f64, 0);
+        assert_eq!(result.sites.len(), points.len());
+        for (original, relaxed) in points.iter().zip(result.sites.iter()) {
+            assert!((original.x - relaxed.x).abs() < 1e-9);
+            assert!((original.y - relaxed.y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn delaunay_voronoi_graph_relaxation_moves_sites_but_preserves_count() {
+        let points = vec![
+            vec2(0.0, 0.0),
+            vec2(4.0, 0.0),
+            vec2(4.0, 4.0),
+            vec2(0.0, 4.0),
+            vec2(0.5, 0.5),
+        ];
+        let result = delaunay_voronoi_graph(&points, 0_f64, 0_f64, 1_f64, 4);
+        assert_eq!(result.sites.len(), points.len());
+        let moved = points.iter().zip(result.sites.iter()).any(|(original, relaxed)| {
+            (original.x - relaxed.x).abs() > 1e-6 || (original.y - relaxed.y).abs() > 1e-6
+        });
+        assert!(moved);
+    }
+}